@@ -1,22 +1,86 @@
-use rand::Rng;
+use rand::{Rng, SeedableRng};
+use rand_pcg::Pcg64;
 use std::time::{Duration, Instant};
-use scoped_threadpool::Pool;
+use rayon::prelude::*;
+use rayon::{BroadcastContext, ThreadPool, ThreadPoolBuilder};
+use crossbeam_utils::CachePadded;
 
+use std::collections::BinaryHeap;
+use std::cmp::Reverse;
+use std::hash::{Hash, Hasher};
+use std::collections::hash_map::DefaultHasher;
 use std::sync::atomic::{AtomicUsize, Ordering};
 
 // Set to true to enable debug output, printing particle positions
 const DEBUG : bool = false;
-const NUM_OF_THREADS: usize = 4;
+
+// Derives the RNG stream a single particle draws from for a given tick.
+// Keyed on the particle's own global index rather than the chunk or worker
+// thread that happens to process it, so the same seed and tick count always
+// reproduce the same movement no matter how many threads the work is split
+// across.
+fn particle_seed(seed: u64, particle_index: usize, tick: u64) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    seed.hash(&mut hasher);
+    particle_index.hash(&mut hasher);
+    tick.hash(&mut hasher);
+    hasher.finish()
+}
+
+// How many work-stealing chunks to offer each worker thread, so a slow chunk
+// on one thread doesn't stall the others the way one-chunk-per-thread would.
+const CHUNKS_PER_THREAD: usize = 4;
+
+fn work_chunk_len(len: usize, num_threads: usize) -> usize {
+    let chunks = (num_threads * CHUNKS_PER_THREAD).max(1);
+    len.div_ceil(chunks)
+}
+
+// Builds the rayon thread pool the simulation runs on, defaulting to rayon's
+// own worker count when the caller doesn't pin one down.
+struct SimulationBuilder {
+    num_threads: Option<usize>,
+}
+
+impl SimulationBuilder {
+    fn new() -> Self {
+        SimulationBuilder { num_threads: None }
+    }
+
+    fn num_threads(mut self, num_threads: usize) -> Self {
+        self.num_threads = Some(num_threads);
+        self
+    }
+
+    fn build(self) -> ThreadPool {
+        let num_threads = self.num_threads.unwrap_or_else(rayon::current_num_threads);
+        ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .build()
+            .expect("failed to build rayon thread pool")
+    }
+}
+
+// Runs `setup` once on every worker thread in `pool` (e.g. to seed a
+// thread-local RNG) before the simulation starts ticking.
+fn broadcast_setup<F>(pool: &ThreadPool, setup: F)
+where
+    F: Fn(BroadcastContext) + Sync,
+{
+    pool.broadcast(setup);
+}
 
 #[derive(Debug, Copy, Clone)]
 struct Particle {
     x: f64,
     y: f64,
+    vx: f64,
+    vy: f64,
 }
 
 impl Particle {
-    fn new(x: f64, y: f64) -> Self {
-        Particle { x, y }
+    fn new(x: f64, y: f64, vx: f64, vy: f64) -> Self {
+        Particle { x, y, vx, vy }
     }
 
     fn collide(&self, other: &Particle, threshold: f64) -> bool {
@@ -27,24 +91,76 @@ impl Particle {
     }
 }
 
-fn thread_move_particles(list: &mut [Particle], enclosure_size: f64) {
-    for particle in list {
+// Uniform grid of cells the size of `cell_size`, used to only test particle
+// pairs (or gather neighbours) that are actually close enough to matter.
+struct SpatialGrid {
+    cols: usize,
+    rows: usize,
+    cell_size: f64,
+    cells: Vec<Vec<usize>>,
+}
+
+impl SpatialGrid {
+    fn new(particles: &[Particle], enclosure_size: f64, cell_size: f64) -> Self {
+        let cols = (enclosure_size / cell_size).ceil() as usize;
+        let rows = cols;
+        let mut cells = vec![Vec::new(); cols * rows];
+
+        for (index, particle) in particles.iter().enumerate() {
+            let cell = Self::cell_of(particle, cell_size, cols, rows);
+            cells[cell].push(index);
+        }
+
+        SpatialGrid { cols, rows, cell_size, cells }
+    }
+
+    fn cell_of(particle: &Particle, cell_size: f64, cols: usize, rows: usize) -> usize {
+        let cx = ((particle.x / cell_size) as usize).min(cols - 1);
+        let cy = ((particle.y / cell_size) as usize).min(rows - 1);
+        cy * cols + cx
+    }
+
+    fn cell_coords(&self, particle: &Particle) -> (usize, usize) {
+        let cx = ((particle.x / self.cell_size) as usize).min(self.cols - 1);
+        let cy = ((particle.y / self.cell_size) as usize).min(self.rows - 1);
+        (cx, cy)
+    }
+
+    fn cell_at(&self, cx: usize, cy: usize) -> &[usize] {
+        &self.cells[cy * self.cols + cx]
+    }
+}
+
+// Moves each particle in `list` (the slice starting at `start_index` within
+// the full particle vector), drawing each one's own RNG stream from
+// `particle_seed` rather than the global `thread_rng()` (see `particle_seed`
+// for why streams are keyed per particle). Out-of-bounds displacements are
+// reflected back into the enclosure instead of rejected, so no sample is
+// ever wasted.
+fn thread_move_particles(list: &mut [Particle], enclosure_size: f64, seed: u64, start_index: usize, tick: u64) {
+    for (offset, particle) in list.iter_mut().enumerate() {
+        let mut rng = Pcg64::seed_from_u64(particle_seed(seed, start_index + offset, tick));
+
         if DEBUG {
             println!("Current position: ({}, {})", particle.x, particle.y)
         }
-        loop {
-            let rand_x: f64 = rand::random();
-            let rand_y: f64 = rand::random();
 
-            let new_x = particle.x + ((rand_x - 0.5) * 2.0);
-            let new_y = particle.y + ((rand_y - 0.5) * 2.0);
+        let mut new_x = particle.x + ((rng.gen::<f64>() - 0.5) * 2.0);
+        let mut new_y = particle.y + ((rng.gen::<f64>() - 0.5) * 2.0);
 
-            if new_x >= 0.0 && new_x <= enclosure_size && new_y >= 0.0 && new_y <= enclosure_size {
-                particle.x = new_x;
-                particle.y = new_y;
-                break;
-            }
+        if new_x < 0.0 {
+            new_x = -new_x;
+        } else if new_x > enclosure_size {
+            new_x = 2.0 * enclosure_size - new_x;
         }
+        if new_y < 0.0 {
+            new_y = -new_y;
+        } else if new_y > enclosure_size {
+            new_y = 2.0 * enclosure_size - new_y;
+        }
+
+        particle.x = new_x.clamp(0.0, enclosure_size);
+        particle.y = new_y.clamp(0.0, enclosure_size);
 
         if DEBUG {
             println!("New position: ({}, {})", particle.x, particle.y);
@@ -52,37 +168,201 @@ fn thread_move_particles(list: &mut [Particle], enclosure_size: f64) {
     }
 }
 
-fn thread_check_collisions(chunk: &[Particle], threshold: f64, collision_counter: &AtomicUsize) {
-    for i in 0..chunk.len() {
-        for j in (i + 1)..chunk.len() {
-            if chunk[i].collide(&chunk[j], threshold) {
-                collision_counter.fetch_add(1, Ordering::SeqCst);
-                if DEBUG {
-                    println!(
-                        "Collision detected between particles at ({}, {}) and ({}, {})",
-                        chunk[i].x, chunk[i].y, chunk[j].x, chunk[j].y
-                    );
+// Alternative to thread_move_particles' random jitter: steers each particle
+// in `chunk` (the slice starting at `start_index` within `snapshot`) using
+// the three boid rules, computed against neighbours gathered from `grid`.
+// Velocity reflects off the enclosure walls rather than rejecting the move.
+fn thread_flock_particles(
+    chunk: &mut [Particle],
+    start_index: usize,
+    snapshot: &[Particle],
+    grid: &SpatialGrid,
+    enclosure_size: f64,
+    params: &FlockParams,
+) {
+    for (offset, particle) in chunk.iter_mut().enumerate() {
+        let me = snapshot[start_index + offset];
+        let (cx, cy) = grid.cell_coords(&me);
+
+        let mut separation = (0.0, 0.0);
+        let mut velocity_sum = (0.0, 0.0);
+        let mut position_sum = (0.0, 0.0);
+        let mut neighbours = 0;
+
+        for dy in -1isize..=1 {
+            for dx in -1isize..=1 {
+                let nx = cx as isize + dx;
+                let ny = cy as isize + dy;
+                if nx < 0 || ny < 0 || nx as usize >= grid.cols || ny as usize >= grid.rows {
+                    continue;
+                }
+
+                for &j in grid.cell_at(nx as usize, ny as usize) {
+                    if start_index + offset == j {
+                        continue;
+                    }
+
+                    let other = snapshot[j];
+                    let dx = other.x - me.x;
+                    let dy = other.y - me.y;
+                    let distance = (dx * dx + dy * dy).sqrt();
+                    if distance == 0.0 {
+                        continue;
+                    }
+
+                    if distance < params.separation_radius {
+                        separation.0 -= dx / distance;
+                        separation.1 -= dy / distance;
+                    }
+
+                    if distance > params.perception_radius {
+                        continue;
+                    }
+
+                    velocity_sum.0 += other.vx;
+                    velocity_sum.1 += other.vy;
+                    position_sum.0 += other.x;
+                    position_sum.1 += other.y;
+                    neighbours += 1;
                 }
             }
         }
+
+        let mut steer_x = separation.0 * params.separation_weight;
+        let mut steer_y = separation.1 * params.separation_weight;
+
+        if neighbours > 0 {
+            let n = neighbours as f64;
+
+            let alignment = (velocity_sum.0 / n - me.vx, velocity_sum.1 / n - me.vy);
+            steer_x += alignment.0 * params.alignment_weight;
+            steer_y += alignment.1 * params.alignment_weight;
+
+            let cohesion = (position_sum.0 / n - me.x, position_sum.1 / n - me.y);
+            steer_x += cohesion.0 * params.cohesion_weight;
+            steer_y += cohesion.1 * params.cohesion_weight;
+        }
+
+        let mut vx = me.vx + steer_x;
+        let mut vy = me.vy + steer_y;
+
+        let speed = (vx * vx + vy * vy).sqrt();
+        if speed > params.max_speed {
+            vx = vx / speed * params.max_speed;
+            vy = vy / speed * params.max_speed;
+        }
+
+        let mut new_x = me.x + vx;
+        let mut new_y = me.y + vy;
+
+        if new_x < 0.0 {
+            new_x = -new_x;
+            vx = -vx;
+        } else if new_x > enclosure_size {
+            new_x = 2.0 * enclosure_size - new_x;
+            vx = -vx;
+        }
+
+        if new_y < 0.0 {
+            new_y = -new_y;
+            vy = -vy;
+        } else if new_y > enclosure_size {
+            new_y = 2.0 * enclosure_size - new_y;
+            vy = -vy;
+        }
+
+        particle.x = new_x;
+        particle.y = new_y;
+        particle.vx = vx;
+        particle.vy = vy;
     }
 }
 
+// Forward-only neighbour offsets (E, SE, S, SW): combined with the cell's own
+// pairwise scan, this covers every unordered pair exactly once.
+const NEIGHBOUR_OFFSETS: [(isize, isize); 4] = [(1, 0), (1, 1), (0, 1), (-1, 1)];
+
+fn check_pair(particles: &[Particle], a: usize, b: usize, threshold: f64, local_count: &mut usize) {
+    if particles[a].collide(&particles[b], threshold) {
+        *local_count += 1;
+        if DEBUG {
+            println!(
+                "Collision detected between particles at ({}, {}) and ({}, {})",
+                particles[a].x, particles[a].y, particles[b].x, particles[b].y
+            );
+        }
+    }
+}
+
+// Counts collisions found in grid row `cy` and returns the tally as a plain
+// local, so the caller can fold it into its shard with a single relaxed add
+// instead of contending on a shared atomic for every pair tested.
+fn thread_check_collisions(particles: &[Particle], grid: &SpatialGrid, cy: usize, threshold: f64) -> usize {
+    let mut local_count = 0usize;
+
+    for cx in 0..grid.cols {
+        let cell = grid.cell_at(cx, cy);
+
+        for i in 0..cell.len() {
+            for j in (i + 1)..cell.len() {
+                check_pair(particles, cell[i], cell[j], threshold, &mut local_count);
+            }
+        }
+
+        for (dx, dy) in NEIGHBOUR_OFFSETS {
+            let nx = cx as isize + dx;
+            let ny = cy as isize + dy;
+            if nx < 0 || ny < 0 || nx as usize >= grid.cols || ny as usize >= grid.rows {
+                continue;
+            }
+            let neighbour = grid.cell_at(nx as usize, ny as usize);
+            for &a in cell {
+                for &b in neighbour {
+                    check_pair(particles, a, b, threshold, &mut local_count);
+                }
+            }
+        }
+    }
+
+    local_count
+}
+
+// Tunable weights for the three boid steering rules, plus the neighbour
+// radii and speed cap they're combined under.
+struct FlockParams {
+    perception_radius: f64,
+    separation_radius: f64,
+    max_speed: f64,
+    separation_weight: f64,
+    alignment_weight: f64,
+    cohesion_weight: f64,
+}
+
 struct ParticleSystem {
     particles: Vec<Particle>,
-    collision_counter: AtomicUsize,
+    collision_shards: Vec<CachePadded<AtomicUsize>>,
+    seed: u64,
+    tick: u64,
+    perception_radius: f64,
+    separation_radius: f64,
+    max_speed: f64,
+    separation_weight: f64,
+    alignment_weight: f64,
+    cohesion_weight: f64,
 }
 
 impl ParticleSystem {
-    fn new(num_particles: usize, max_x: f64, max_y: f64) -> Self {
+    fn new(num_particles: usize, max_x: f64, max_y: f64, num_threads: usize, seed: u64) -> Self {
 
         let mut particles = Vec::with_capacity(num_particles);
-        let mut rng = rand::thread_rng();
+        let mut rng = Pcg64::seed_from_u64(seed);
 
         for _ in 0..num_particles {
             let x = rng.gen_range(0.0..max_x);
             let y = rng.gen_range(0.0..max_y);
-            particles.push(Particle::new(x, y));
+            let vx = rng.gen_range(-1.0..1.0);
+            let vy = rng.gen_range(-1.0..1.0);
+            particles.push(Particle::new(x, y, vx, vy));
         }
 
         if DEBUG {
@@ -93,68 +373,434 @@ impl ParticleSystem {
         }
         ParticleSystem {
             particles,
-            collision_counter: AtomicUsize::new(0),
+            collision_shards: (0..num_threads).map(|_| CachePadded::new(AtomicUsize::new(0))).collect(),
+            seed,
+            tick: 0,
+            perception_radius: 1.0,
+            separation_radius: 0.4,
+            max_speed: 2.0,
+            separation_weight: 1.5,
+            alignment_weight: 1.0,
+            cohesion_weight: 1.0,
         }    }
 
-    fn move_particles(&mut self, enclosure_size: f64, pool: &mut Pool) {
-        pool.scoped(|scope| {
-            for chunk in self.particles.chunks_mut(NUM_OF_THREADS) {
-                scope.execute(move || {
-                    thread_move_particles(chunk, enclosure_size);
-                });
-            }
+    fn move_particles(&mut self, enclosure_size: f64, pool: &ThreadPool) {
+        let chunk_len = work_chunk_len(self.particles.len(), pool.current_num_threads());
+        let seed = self.seed;
+        let tick = self.tick;
+        pool.install(|| {
+            self.particles.par_chunks_mut(chunk_len).enumerate().for_each(|(chunk_index, chunk)| {
+                let start_index = chunk_index * chunk_len;
+                thread_move_particles(chunk, enclosure_size, seed, start_index, tick);
+            });
         });
+        self.tick += 1;
     }
 
-    fn check_collisions(&self, threshold: f64, pool: &mut Pool) {
-        pool.scoped(|scope| {
-            for chunk in self.particles.chunks(NUM_OF_THREADS) {
-                let collision_counter = &self.collision_counter;
-                scope.execute(move || {
-                    thread_check_collisions(chunk, threshold, collision_counter);
-                });
-            }
+    fn flock_particles(&mut self, enclosure_size: f64, pool: &ThreadPool) {
+        // Sized off the larger of the two radii so a separation radius tuned
+        // above the perception radius still gets a 3x3 cell search wide
+        // enough to see every particle it needs to push away from.
+        let grid_radius = self.perception_radius.max(self.separation_radius);
+        let grid = SpatialGrid::new(&self.particles, enclosure_size, grid_radius);
+        let snapshot = self.particles.clone();
+        let params = FlockParams {
+            perception_radius: self.perception_radius,
+            separation_radius: self.separation_radius,
+            max_speed: self.max_speed,
+            separation_weight: self.separation_weight,
+            alignment_weight: self.alignment_weight,
+            cohesion_weight: self.cohesion_weight,
+        };
+        let chunk_len = work_chunk_len(snapshot.len(), pool.current_num_threads());
+
+        pool.install(|| {
+            self.particles.par_chunks_mut(chunk_len).enumerate().for_each(|(chunk_index, chunk)| {
+                let start_index = chunk_index * chunk_len;
+                thread_flock_particles(chunk, start_index, &snapshot, &grid, enclosure_size, &params);
+            });
         });
     }
+
+    fn check_collisions(&self, enclosure_size: f64, threshold: f64, pool: &ThreadPool) {
+        let grid = SpatialGrid::new(&self.particles, enclosure_size, threshold);
+        let rows_chunk_len = work_chunk_len(grid.rows, pool.current_num_threads());
+        let particles = &self.particles;
+        let shards = &self.collision_shards;
+
+        pool.install(|| {
+            (0..grid.rows).into_par_iter().with_min_len(rows_chunk_len.max(1)).for_each(|cy| {
+                let local_count = thread_check_collisions(particles, &grid, cy, threshold);
+                // Credit the shard of whichever worker actually ran this row,
+                // not a static chunk index, since rayon steals work between
+                // threads rather than handing out one fixed slice each.
+                let shard_index = rayon::current_thread_index().unwrap_or(0) % shards.len();
+                shards[shard_index].fetch_add(local_count, Ordering::Relaxed);
+            });
+        });
+    }
+
+    fn total_collisions(&self) -> usize {
+        self.collision_shards.iter().map(|shard| shard.load(Ordering::Relaxed)).sum()
+    }
+}
+
+// Alternative to ParticleSystem's random-walk model: particles carry a
+// velocity and move continuously, with collisions resolved exactly via a
+// time-ordered event queue instead of a per-tick overlap scan.
+#[derive(Debug, Copy, Clone)]
+struct EventParticle {
+    x: f64,
+    y: f64,
+    vx: f64,
+    vy: f64,
+}
+
+#[derive(Debug, Copy, Clone)]
+enum Axis {
+    X,
+    Y,
+}
+
+// Records a candidate collision between particles `a` and `b`, or (when `b`
+// is `None`) a particle-wall bounce along `wall_axis`. `a_count`/`b_count`
+// snapshot each particle's mutation counter at prediction time so a stale
+// event (superseded by an earlier collision) can be detected and discarded
+// when it is popped.
+#[derive(Debug, Copy, Clone)]
+struct Event {
+    time: f64,
+    a: usize,
+    b: Option<usize>,
+    a_count: u64,
+    b_count: u64,
+    wall_axis: Option<Axis>,
+}
+
+impl PartialEq for Event {
+    fn eq(&self, other: &Self) -> bool {
+        self.time == other.time
+    }
+}
+
+impl Eq for Event {}
+
+impl PartialOrd for Event {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Event {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.time.partial_cmp(&other.time).expect("event time is never NaN")
+    }
+}
+
+// Solves for the smallest t > 0 at which particles `i` and `j` (radius
+// `radius`) touch, given their current positions/velocities at `current_time`.
+fn predict_pair(particles: &[EventParticle], i: usize, j: usize, radius: f64, current_time: f64) -> Option<f64> {
+    let dp_x = particles[j].x - particles[i].x;
+    let dp_y = particles[j].y - particles[i].y;
+    let dv_x = particles[j].vx - particles[i].vx;
+    let dv_y = particles[j].vy - particles[i].vy;
+
+    let dv_dot_dp = dv_x * dp_x + dv_y * dp_y;
+    if dv_dot_dp >= 0.0 {
+        return None;
+    }
+
+    let dv_dot_dv = dv_x * dv_x + dv_y * dv_y;
+    let dp_dot_dp = dp_x * dp_x + dp_y * dp_y;
+    let sigma = 2.0 * radius;
+    let discriminant = dv_dot_dp * dv_dot_dp - dv_dot_dv * (dp_dot_dp - sigma * sigma);
+    if discriminant < 0.0 {
+        return None;
+    }
+
+    let t = -(dv_dot_dp + discriminant.sqrt()) / dv_dot_dv;
+    if t <= 0.0 {
+        return None;
+    }
+    Some(current_time + t)
+}
+
+// Absolute time (`current_time` plus the time to impact) at which `particle`
+// reaches the enclosure wall along each axis, given its current velocity.
+// `None` when it is stationary along that axis.
+fn predict_walls(particle: &EventParticle, radius: f64, enclosure_size: f64, current_time: f64) -> (Option<f64>, Option<f64>) {
+    let tx = if particle.vx > 0.0 {
+        Some((enclosure_size - radius - particle.x) / particle.vx)
+    } else if particle.vx < 0.0 {
+        Some((radius - particle.x) / particle.vx)
+    } else {
+        None
+    };
+
+    let ty = if particle.vy > 0.0 {
+        Some((enclosure_size - radius - particle.y) / particle.vy)
+    } else if particle.vy < 0.0 {
+        Some((radius - particle.y) / particle.vy)
+    } else {
+        None
+    };
+
+    (tx.map(|t| current_time + t), ty.map(|t| current_time + t))
+}
+
+struct EventDrivenSystem {
+    particles: Vec<EventParticle>,
+    counters: Vec<u64>,
+    enclosure_size: f64,
+    radius: f64,
+    events: BinaryHeap<Reverse<Event>>,
+}
+
+impl EventDrivenSystem {
+    fn new(num_particles: usize, enclosure_size: f64, radius: f64) -> Self {
+        let mut rng = rand::thread_rng();
+        let mut particles = Vec::with_capacity(num_particles);
+
+        for _ in 0..num_particles {
+            particles.push(EventParticle {
+                x: rng.gen_range(radius..(enclosure_size - radius)),
+                y: rng.gen_range(radius..(enclosure_size - radius)),
+                vx: rng.gen_range(-1.0..1.0),
+                vy: rng.gen_range(-1.0..1.0),
+            });
+        }
+
+        let mut system = EventDrivenSystem {
+            counters: vec![0; particles.len()],
+            particles,
+            enclosure_size,
+            radius,
+            events: BinaryHeap::new(),
+        };
+
+        for i in 0..system.particles.len() {
+            system.schedule_events_for(i, 0.0);
+        }
+
+        system
+    }
+
+    fn schedule_events_for(&mut self, i: usize, current_time: f64) {
+        let (tx, ty) = predict_walls(&self.particles[i], self.radius, self.enclosure_size, current_time);
+        if let Some(time) = tx {
+            self.events.push(Reverse(Event { time, a: i, b: None, a_count: self.counters[i], b_count: 0, wall_axis: Some(Axis::X) }));
+        }
+        if let Some(time) = ty {
+            self.events.push(Reverse(Event { time, a: i, b: None, a_count: self.counters[i], b_count: 0, wall_axis: Some(Axis::Y) }));
+        }
+
+        for j in 0..self.particles.len() {
+            if j == i {
+                continue;
+            }
+            if let Some(time) = predict_pair(&self.particles, i, j, self.radius, current_time) {
+                let (a, b) = (i.min(j), i.max(j));
+                self.events.push(Reverse(Event { time, a, b: Some(b), a_count: self.counters[a], b_count: self.counters[b], wall_axis: None }));
+            }
+        }
+    }
+
+    fn advance_to(&mut self, time: f64, current_time: f64) {
+        let dt = time - current_time;
+        for particle in &mut self.particles {
+            particle.x += particle.vx * dt;
+            particle.y += particle.vy * dt;
+        }
+    }
+
+    fn resolve_collision(&mut self, a: usize, b: usize) {
+        let dp_x = self.particles[b].x - self.particles[a].x;
+        let dp_y = self.particles[b].y - self.particles[a].y;
+        let distance = (dp_x * dp_x + dp_y * dp_y).sqrt();
+        let (nx, ny) = (dp_x / distance, dp_y / distance);
+
+        let dv_x = self.particles[b].vx - self.particles[a].vx;
+        let dv_y = self.particles[b].vy - self.particles[a].vy;
+        let dv_dot_n = dv_x * nx + dv_y * ny;
+
+        // Equal masses: the impulse just exchanges the velocity component
+        // along the contact normal.
+        self.particles[a].vx += dv_dot_n * nx;
+        self.particles[a].vy += dv_dot_n * ny;
+        self.particles[b].vx -= dv_dot_n * nx;
+        self.particles[b].vy -= dv_dot_n * ny;
+    }
+
+    fn resolve_wall_bounce(&mut self, a: usize, axis: Axis) {
+        match axis {
+            Axis::X => self.particles[a].vx = -self.particles[a].vx,
+            Axis::Y => self.particles[a].vy = -self.particles[a].vy,
+        }
+    }
+
+    // Runs the simulation until `duration` and returns the number of
+    // collisions (wall bounces are not counted).
+    fn run(&mut self, duration: f64) -> usize {
+        let mut current_time = 0.0;
+        let mut collisions = 0;
+
+        while let Some(Reverse(event)) = self.events.pop() {
+            if event.time > duration {
+                break;
+            }
+
+            // Lazy invalidation: drop this event if either particle has
+            // collided or bounced off a wall since it was scheduled.
+            if event.a_count != self.counters[event.a] {
+                continue;
+            }
+            if let Some(b) = event.b {
+                if event.b_count != self.counters[b] {
+                    continue;
+                }
+            }
+
+            self.advance_to(event.time, current_time);
+            current_time = event.time;
+
+            match (event.b, event.wall_axis) {
+                (Some(b), _) => {
+                    self.resolve_collision(event.a, b);
+                    self.counters[event.a] += 1;
+                    self.counters[b] += 1;
+                    collisions += 1;
+                    self.schedule_events_for(event.a, current_time);
+                    self.schedule_events_for(b, current_time);
+                }
+                (None, Some(axis)) => {
+                    self.resolve_wall_bounce(event.a, axis);
+                    self.counters[event.a] += 1;
+                    self.schedule_events_for(event.a, current_time);
+                }
+                (None, None) => unreachable!("a non-wall event always has a pair or an axis"),
+            }
+        }
+
+        collisions
+    }
 }
 
 fn main() {
     let enclosure_size = 10.0;
     let collision_threshold = 0.1;
 
-    let mut particle_system = ParticleSystem::new(100, enclosure_size, enclosure_size);
+    if std::env::args().any(|arg| arg == "--event-driven") {
+        let mut event_system = EventDrivenSystem::new(100, enclosure_size, collision_threshold / 2.0);
+        let collisions = event_system.run(10.0);
+        println!("Event-driven simulation produced {} collisions", collisions);
+        return;
+    }
+
+    let mut builder = SimulationBuilder::new();
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(index) = args.iter().position(|arg| arg == "--threads") {
+        if let Some(num_threads) = args.get(index + 1).and_then(|value| value.parse().ok()) {
+            builder = builder.num_threads(num_threads);
+        }
+    }
+    let pool = builder.build();
+
+    let seed = args.iter().position(|arg| arg == "--seed")
+        .and_then(|index| args.get(index + 1))
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_else(rand::random);
 
-    let start = Instant::now();
-    let duration = Duration::new(10, 0);
+    let mut particle_system = ParticleSystem::new(100, enclosure_size, enclosure_size, pool.current_num_threads(), seed);
 
-    let mut count = 0;
+    broadcast_setup(&pool, |ctx| {
+        if DEBUG {
+            println!("Worker {} ready", ctx.index());
+        }
+    });
 
     println!("\n\nMoving particles...");
 
-    // initialise thread pool
-    let mut pool = Pool::new(NUM_OF_THREADS as u32);
-
-    while Instant::now() - start < duration {
-        count += 1;
-        pool.scoped(|scope| {
-            // Move particles
-            for chunk in particle_system.particles.chunks_mut(NUM_OF_THREADS) {
-                scope.execute(move || {
-                    thread_move_particles(chunk, enclosure_size);
-                });
-            }
+    let flocking = std::env::args().any(|arg| arg == "--flock");
 
-            // Check collisions
-            for chunk in particle_system.particles.chunks(NUM_OF_THREADS) {
-                let collision_counter = &particle_system.collision_counter;
-                scope.execute(move || {
-                    thread_check_collisions(chunk, collision_threshold, collision_counter);
-                });
-            }
-        });
+    let ticks = args.iter().position(|arg| arg == "--ticks")
+        .and_then(|index| args.get(index + 1))
+        .and_then(|value| value.parse().ok());
+
+    let count = if let Some(ticks) = ticks {
+        run_ticks(&mut particle_system, &pool, enclosure_size, collision_threshold, flocking, ticks);
+        ticks
+    } else {
+        let start = Instant::now();
+        let duration = Duration::new(10, 0);
+        let mut count = 0;
+        while Instant::now() - start < duration {
+            count += 1;
+            step(&mut particle_system, &pool, enclosure_size, collision_threshold, flocking);
+        }
+        println!("Particles moved {} times in 10 seconds", count);
+        count
+    };
+
+    if ticks.is_some() {
+        println!("Particles moved {} times", count);
     }
 
-    println!("Particles moved {} times in 10 seconds", count);
+    println!("Total number of collisions: {}", particle_system.total_collisions());
+}
+
+// Advances `system` by one tick: a movement or flocking pass followed by
+// collision detection.
+fn step(system: &mut ParticleSystem, pool: &ThreadPool, enclosure_size: f64, collision_threshold: f64, flocking: bool) {
+    if flocking {
+        system.flock_particles(enclosure_size, pool);
+    } else {
+        system.move_particles(enclosure_size, pool);
+    }
+    system.check_collisions(enclosure_size, collision_threshold, pool);
+}
+
+// Runs the simulation for a fixed number of ticks rather than a wall-clock
+// duration, so a given seed and tick count always reproduce the same
+// collision count regardless of how fast the machine runs.
+fn run_ticks(system: &mut ParticleSystem, pool: &ThreadPool, enclosure_size: f64, collision_threshold: f64, flocking: bool, ticks: usize) {
+    for _ in 0..ticks {
+        step(system, pool, enclosure_size, collision_threshold, flocking);
+    }
+}
 
-    println!("Total number of collisions: {}", particle_system.collision_counter.load(Ordering::SeqCst));
-}
\ No newline at end of file
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_reproduces_same_collision_count() {
+        let enclosure_size = 10.0;
+        let collision_threshold = 0.1;
+        let pool = SimulationBuilder::new().num_threads(4).build();
+
+        let mut a = ParticleSystem::new(100, enclosure_size, enclosure_size, pool.current_num_threads(), 42);
+        let mut b = ParticleSystem::new(100, enclosure_size, enclosure_size, pool.current_num_threads(), 42);
+
+        run_ticks(&mut a, &pool, enclosure_size, collision_threshold, false, 200);
+        run_ticks(&mut b, &pool, enclosure_size, collision_threshold, false, 200);
+
+        assert_eq!(a.total_collisions(), b.total_collisions());
+    }
+
+    #[test]
+    fn same_seed_reproduces_same_collision_count_regardless_of_thread_count() {
+        let enclosure_size = 10.0;
+        let collision_threshold = 0.1;
+
+        let pool_a = SimulationBuilder::new().num_threads(1).build();
+        let pool_b = SimulationBuilder::new().num_threads(4).build();
+
+        let mut a = ParticleSystem::new(100, enclosure_size, enclosure_size, pool_a.current_num_threads(), 42);
+        let mut b = ParticleSystem::new(100, enclosure_size, enclosure_size, pool_b.current_num_threads(), 42);
+
+        run_ticks(&mut a, &pool_a, enclosure_size, collision_threshold, false, 200);
+        run_ticks(&mut b, &pool_b, enclosure_size, collision_threshold, false, 200);
+
+        assert_eq!(a.total_collisions(), b.total_collisions());
+    }
+}